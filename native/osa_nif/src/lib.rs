@@ -3,6 +3,12 @@ mod text;
 
 rustler::init!("Elixir.OptimalSystemAgent.NIF", [
     tokenizer::count_tokens,
+    tokenizer::count_tokens_for_model,
+    tokenizer::token_budget,
+    tokenizer::estimate_tokens,
     text::calculate_weight,
+    text::calculate_weight_with,
+    text::information_density,
     text::word_count,
+    text::text_stats,
 ]);
@@ -1,5 +1,9 @@
 use regex::Regex;
-use std::sync::OnceLock;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+use unicode_segmentation::UnicodeSegmentation;
 
 static URGENCY_RE: OnceLock<Regex> = OnceLock::new();
 static NOISE_RE: OnceLock<Regex> = OnceLock::new();
@@ -42,7 +46,227 @@ fn calculate_weight(text: &str) -> f64 {
     result.clamp(0.0, 1.0)
 }
 
+#[derive(rustler::NifMap)]
+pub struct WeightConfig {
+    pub base: f64,
+    pub length_divisor: f64,
+    pub length_cap: f64,
+    pub question_bonus: f64,
+    pub urgency_bonus: f64,
+    pub urgency_keywords: Vec<String>,
+    pub noise_penalty: f64,
+    pub noise_keywords: Vec<String>,
+}
+
+// Capped at this many distinct keyword sets; oldest entry is evicted once
+// full, since operators editing keyword lists live would otherwise leak an
+// unbounded entry per edit for the life of the BEAM node.
+const KEYWORD_REGEX_CACHE_CAP: usize = 256;
+
+#[derive(Default)]
+struct KeywordRegexCache {
+    entries: HashMap<u64, Regex>,
+    order: VecDeque<u64>,
+}
+
+impl KeywordRegexCache {
+    fn get_or_compile(&mut self, key: u64, pattern: &str) -> Regex {
+        if let Some(regex) = self.entries.get(&key) {
+            return regex.clone();
+        }
+
+        if self.order.len() >= KEYWORD_REGEX_CACHE_CAP {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        let regex = Regex::new(pattern).expect("invalid keyword regex");
+        self.order.push_back(key);
+        self.entries.insert(key, regex.clone());
+        regex
+    }
+}
+
+static KEYWORD_REGEX_CACHE: OnceLock<Mutex<KeywordRegexCache>> = OnceLock::new();
+
+fn keyword_regex_cache() -> &'static Mutex<KeywordRegexCache> {
+    KEYWORD_REGEX_CACHE.get_or_init(|| Mutex::new(KeywordRegexCache::default()))
+}
+
+fn keyword_regex(keywords: &[String]) -> Regex {
+    let pattern = if keywords.is_empty() {
+        // Matches no character, so an empty list is "never match" rather
+        // than the empty alternation `()`, which matches everything.
+        r"[^\s\S]".to_string()
+    } else {
+        format!(
+            r"(?i)\b({})\b",
+            keywords
+                .iter()
+                .map(|keyword| regex::escape(keyword))
+                .collect::<Vec<_>>()
+                .join("|")
+        )
+    };
+
+    // Canonicalize on a sorted, lowercased keyword set rather than the raw
+    // pattern string, so equivalent lists in a different order (or with
+    // different casing) share one cache entry instead of leaking a new one.
+    let mut canonical: Vec<String> = keywords.iter().map(|k| k.to_lowercase()).collect();
+    canonical.sort();
+
+    let mut hasher = DefaultHasher::new();
+    canonical.hash(&mut hasher);
+    let key = hasher.finish();
+
+    keyword_regex_cache()
+        .lock()
+        .expect("keyword regex cache poisoned")
+        .get_or_compile(key, &pattern)
+}
+
+/// Same scoring as `calculate_weight`, with every knob caller-supplied.
+#[rustler::nif]
+fn calculate_weight_with(text: &str, config: WeightConfig) -> f64 {
+    let length_bonus: f64 =
+        (text.chars().count() as f64 / config.length_divisor).min(config.length_cap);
+
+    let question_bonus: f64 = if text.contains('?') {
+        config.question_bonus
+    } else {
+        0.0
+    };
+
+    let urgency_bonus: f64 = if keyword_regex(&config.urgency_keywords).is_match(text) {
+        config.urgency_bonus
+    } else {
+        0.0
+    };
+
+    let noise_penalty: f64 = if keyword_regex(&config.noise_keywords).is_match(text) {
+        config.noise_penalty
+    } else {
+        0.0
+    };
+
+    let result = config.base + length_bonus + question_bonus + urgency_bonus + noise_penalty;
+    result.clamp(0.0, 1.0)
+}
+
+// Fixed reference alphabet size (the 128 ASCII code points) to normalize entropy
+// against. Normalizing by the distinct-character count of the message
+// itself would let short text drawn from only a few symbols (e.g. "ok ok
+// ok") score near the maximum, since that handful of symbols is already
+// close to uniformly distributed.
+const REFERENCE_ALPHABET_SIZE: f64 = 128.0;
+
+fn normalized_entropy(text: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in text.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+
+    let total = text.chars().count();
+    if total == 0 || counts.len() < 2 {
+        return 0.0;
+    }
+
+    let entropy: f64 = counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum();
+
+    (entropy / REFERENCE_ALPHABET_SIZE.log2()).min(1.0)
+}
+
+// Trigrams rather than whitespace tokens, so single-token repetition
+// ("asdfasdfasdf", no spaces) is caught the same as repeated words.
+const TYPE_RATIO_NGRAM_SIZE: usize = 3;
+
+fn token_type_ratio(text: &str) -> f64 {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.is_empty() {
+        return 0.0;
+    }
+    if chars.len() < TYPE_RATIO_NGRAM_SIZE {
+        return 1.0;
+    }
+
+    let ngrams: Vec<String> = chars
+        .windows(TYPE_RATIO_NGRAM_SIZE)
+        .map(|window| window.iter().collect())
+        .collect();
+
+    let unique: std::collections::HashSet<&str> = ngrams.iter().map(String::as_str).collect();
+    unique.len() as f64 / ngrams.len() as f64
+}
+
+/// Blends normalized character entropy with the unique/total character-trigram ratio.
+#[rustler::nif]
+fn information_density(text: &str) -> f64 {
+    let entropy = normalized_entropy(text);
+    let type_ratio = token_type_ratio(text);
+
+    (0.7 * entropy + 0.3 * type_ratio).clamp(0.0, 1.0)
+}
+
 #[rustler::nif]
 fn word_count(text: &str) -> usize {
     text.split_whitespace().count()
 }
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x4E00..=0x9FFF
+            | 0x3400..=0x4DBF
+            | 0x20000..=0x2A6DF
+            | 0x3040..=0x309F
+            | 0x30A0..=0x30FF
+            | 0xAC00..=0xD7A3
+    )
+}
+
+/// Each run of `.`/`!`/`?` counts as one sentence boundary.
+fn sentence_count(text: &str) -> usize {
+    let mut sentences = 0;
+    let mut has_content = false;
+
+    for grapheme in text.graphemes(true) {
+        if matches!(grapheme, "." | "!" | "?") {
+            if has_content {
+                sentences += 1;
+                has_content = false;
+            }
+        } else if !grapheme.trim().is_empty() {
+            has_content = true;
+        }
+    }
+
+    if has_content {
+        sentences += 1;
+    }
+
+    sentences
+}
+
+#[derive(rustler::NifMap)]
+pub struct TextStats {
+    pub words: usize,
+    pub graphemes: usize,
+    pub sentences: usize,
+    pub cjk_chars: usize,
+}
+
+#[rustler::nif]
+fn text_stats(text: &str) -> TextStats {
+    TextStats {
+        words: text.unicode_words().count(),
+        graphemes: text.graphemes(true).count(),
+        sentences: sentence_count(text),
+        cjk_chars: text.chars().filter(|c| is_cjk(*c)).count(),
+    }
+}
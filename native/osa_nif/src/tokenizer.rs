@@ -1,18 +1,130 @@
 use std::sync::OnceLock;
 use tiktoken_rs::CoreBPE;
+use unicode_segmentation::UnicodeSegmentation;
 
-static ENCODING: OnceLock<CoreBPE> = OnceLock::new();
+static CL100K_BASE: OnceLock<CoreBPE> = OnceLock::new();
+static O200K_BASE: OnceLock<CoreBPE> = OnceLock::new();
+static P50K_BASE: OnceLock<CoreBPE> = OnceLock::new();
+static R50K_BASE: OnceLock<CoreBPE> = OnceLock::new();
 
-fn get_encoding() -> &'static CoreBPE {
-    ENCODING.get_or_init(|| {
+fn cl100k_base() -> &'static CoreBPE {
+    CL100K_BASE.get_or_init(|| {
         tiktoken_rs::cl100k_base().expect("failed to load cl100k_base encoding")
     })
 }
 
-#[rustler::nif(schedule = "DirtyCpu")]
-fn count_tokens(text: &str) -> usize {
-    match std::panic::catch_unwind(|| get_encoding().encode_ordinary(text).len()) {
+fn o200k_base() -> &'static CoreBPE {
+    O200K_BASE.get_or_init(|| {
+        tiktoken_rs::o200k_base().expect("failed to load o200k_base encoding")
+    })
+}
+
+fn p50k_base() -> &'static CoreBPE {
+    P50K_BASE.get_or_init(|| {
+        tiktoken_rs::p50k_base().expect("failed to load p50k_base encoding")
+    })
+}
+
+fn r50k_base() -> &'static CoreBPE {
+    R50K_BASE.get_or_init(|| {
+        tiktoken_rs::r50k_base().expect("failed to load r50k_base encoding")
+    })
+}
+
+/// Unknown models default to `o200k_base`.
+fn encoding_for_model(model: &str) -> (&'static str, &'static CoreBPE) {
+    let name = if model.starts_with("gpt-4o") || model.starts_with("o1") || model.starts_with("o3")
+    {
+        "o200k_base"
+    } else if model.starts_with("gpt-4") || model.starts_with("gpt-3.5") || model.starts_with("text-embedding")
+    {
+        "cl100k_base"
+    } else if model.starts_with("text-davinci") || model.starts_with("code-davinci") {
+        "p50k_base"
+    } else if model.starts_with("davinci")
+        || model.starts_with("curie")
+        || model.starts_with("babbage")
+        || model.starts_with("ada")
+    {
+        "r50k_base"
+    } else {
+        "o200k_base"
+    };
+
+    let encoding = match name {
+        "cl100k_base" => cl100k_base(),
+        "p50k_base" => p50k_base(),
+        "r50k_base" => r50k_base(),
+        _ => o200k_base(),
+    };
+
+    (name, encoding)
+}
+
+fn count_tokens_impl(text: &str) -> usize {
+    match std::panic::catch_unwind(|| cl100k_base().encode_ordinary(text).len()) {
         Ok(count) => count,
         Err(_) => 0,
     }
 }
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn count_tokens(text: &str) -> usize {
+    count_tokens_impl(text)
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn count_tokens_for_model(model: &str, text: &str) -> (usize, &'static str) {
+    let (encoding_name, encoding) = encoding_for_model(model);
+    match std::panic::catch_unwind(|| encoding.encode_ordinary(text).len()) {
+        Ok(count) => (count, encoding_name),
+        Err(_) => (0, encoding_name),
+    }
+}
+
+#[derive(rustler::NifMap)]
+pub struct TokenBudget {
+    pub used: usize,
+    pub remaining: usize,
+    pub over_budget: bool,
+}
+
+#[rustler::nif(schedule = "DirtyCpu")]
+fn token_budget(text: &str, max_tokens: usize, reserve: usize) -> TokenBudget {
+    let used = count_tokens_impl(text);
+    let budget = max_tokens.saturating_sub(reserve);
+
+    TokenBudget {
+        used,
+        remaining: budget.saturating_sub(used),
+        over_budget: used > budget,
+    }
+}
+
+/// Counts contiguous runs of ASCII punctuation, e.g. `"wait... really?!"` is two runs.
+fn punctuation_runs(text: &str) -> usize {
+    let mut runs = 0;
+    let mut in_run = false;
+
+    for c in text.chars() {
+        if c.is_ascii_punctuation() {
+            if !in_run {
+                runs += 1;
+                in_run = true;
+            }
+        } else {
+            in_run = false;
+        }
+    }
+
+    runs
+}
+
+/// Skips the BPE tables; cheaper approximation for hot paths.
+#[rustler::nif]
+fn estimate_tokens(text: &str) -> usize {
+    let words = text.unicode_words().count();
+    let non_ascii_graphemes = text.graphemes(true).filter(|g| !g.is_ascii()).count();
+
+    words + non_ascii_graphemes / 2 + punctuation_runs(text)
+}